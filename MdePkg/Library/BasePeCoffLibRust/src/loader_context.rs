@@ -51,11 +51,291 @@ pub enum PeCoffImageError {
   ImageErrorUnsupported,
 }
 
-impl From<goblin::error::Error> for PeCoffImageError {
-    fn from(err: goblin::error::Error) -> Self {
-      // Let's just say -- for now -- than any Goblin error is an image error.
-      PeCoffImageError::ImageErrorImageRead
+// REF: MdePkg/Include/IndustryStandard/PeImage.h -- IMAGE_FILE_RELOCS_STRIPPED
+const IMAGE_FILE_RELOCS_STRIPPED: u16 = 0x0001;
+
+// REF: MdePkg/Include/IndustryStandard/PeImage.h -- machine types this loader
+// knows how to load and relocate. Anything else is rejected up front rather
+// than trusted to "probably work".
+const IMAGE_FILE_MACHINE_I386:   u16 = 0x014c;
+// UEFI identifies 32-bit ARM images by ARMTHUMB_MIXED, not the Windows
+// "ARM little endian" value (0x01c0) -- EDK2 never emits or loads the latter.
+const IMAGE_FILE_MACHINE_ARMTHUMB_MIXED: u16 = 0x01c2;
+const IMAGE_FILE_MACHINE_ARM64:  u16 = 0xaa64;
+const IMAGE_FILE_MACHINE_X64:    u16 = 0x8664;
+const IMAGE_FILE_MACHINE_RISCV64: u16 = 0x5064;
+const IMAGE_FILE_MACHINE_EBC:    u16 = 0x0ebc;
+
+const SUPPORTED_MACHINE_TYPES: &[u16] = &[
+  IMAGE_FILE_MACHINE_I386,
+  IMAGE_FILE_MACHINE_ARMTHUMB_MIXED,
+  IMAGE_FILE_MACHINE_ARM64,
+  IMAGE_FILE_MACHINE_X64,
+  IMAGE_FILE_MACHINE_RISCV64,
+  IMAGE_FILE_MACHINE_EBC,
+];
+
+// REF: MdePkg/Include/IndustryStandard/PeImage.h -- EFI_IMAGE_SUBSYSTEM_*
+const IMAGE_SUBSYSTEM_EFI_APPLICATION: u16 = 10;
+const IMAGE_SUBSYSTEM_EFI_BOOT_SERVICE_DRIVER: u16 = 11;
+const IMAGE_SUBSYSTEM_EFI_RUNTIME_DRIVER: u16 = 12;
+
+// REF: MdePkg/Include/IndustryStandard/PeImage.h -- EFI_TE_IMAGE_HEADER_SIGNATURE ("VZ")
+const TE_IMAGE_HEADER_SIGNATURE: u16 = 0x5a56;
+// sizeof(EFI_TE_IMAGE_HEADER)
+const TE_IMAGE_HEADER_SIZE: usize = 40;
+// sizeof(EFI_IMAGE_SECTION_HEADER), shared between the PE and TE section tables.
+const IMAGE_SECTION_HEADER_SIZE: usize = 40;
+
+// REF: MdePkg/Include/IndustryStandard/PeImage.h -- EFI_IMAGE_DOS_HEADER
+// Only e_magic (offset 0) and e_lfanew (offset 0x3c) are ever consulted, but
+// the header is read as a single fixed-size block since both fields live
+// within it.
+const DOS_HEADER_SIZE: usize = 64;
+const DOS_IMAGE_HEADER_SIGNATURE: u16 = 0x5a4d; // "MZ"
+const DOS_HEADER_LFANEW_OFFSET: usize = 0x3c;
+
+// REF: MdePkg/Include/IndustryStandard/PeImage.h -- EFI_IMAGE_NT_SIGNATURE ("PE\0\0")
+const PE_IMAGE_HEADER_SIGNATURE: u32 = 0x0000_4550;
+const PE_SIGNATURE_SIZE: usize = 4;
+// sizeof(EFI_IMAGE_FILE_HEADER), the fixed fields immediately following the
+// 4-byte "PE\0\0" signature.
+const COFF_HEADER_SIZE: usize = 20;
+
+// REF: MdePkg/Include/IndustryStandard/PeImage.h -- EFI_IMAGE_NT_OPTIONAL_HDR32/64_MAGIC
+const OPTIONAL_HEADER_MAGIC_PE32: u16 = 0x10b;
+const OPTIONAL_HEADER_MAGIC_PE32_PLUS: u16 = 0x20b;
+
+// Untrusted, attacker-controlled counts/sizes are bounds-checked against
+// these before they're used to size a read or an allocation. A well-formed
+// image never comes close; a malformed one that claims otherwise is
+// rejected outright rather than trusted.
+const MAX_OPTIONAL_HEADER_SIZE: usize = 256;
+const MAX_SECTION_COUNT: usize = 96;
+const MAX_DATA_DIRECTORY_ENTRIES: usize = 16;
+
+// REF: MdePkg/Include/IndustryStandard/PeImage.h -- EFI_IMAGE_DIRECTORY_ENTRY_BASERELOC/DEBUG
+const IMAGE_DIRECTORY_ENTRY_BASERELOC: usize = 5;
+const IMAGE_DIRECTORY_ENTRY_DEBUG: usize = 6;
+
+// REF: MdePkg/Include/IndustryStandard/PeImage.h -- EFI_IMAGE_DEBUG_TYPE_CODEVIEW
+const IMAGE_DEBUG_TYPE_CODEVIEW: u32 = 2;
+// sizeof(EFI_IMAGE_DEBUG_DIRECTORY_ENTRY)
+const IMAGE_DEBUG_DIRECTORY_ENTRY_SIZE: usize = 28;
+
+// REF: MdePkg/Include/IndustryStandard/PeImage.h -- CODEVIEW_SIGNATURE_RSDS/NB10
+//
+// The two CodeView record flavors this loader recognizes, each a 4-byte
+// signature followed by a fixed-size identifier and a NUL-terminated UTF-8
+// PDB path. Only the identifier width differs between the two.
+const CODEVIEW_SIGNATURE_RSDS: u32 = 0x5344_5352; // "RSDS"
+const CODEVIEW_SIGNATURE_NB10: u32 = 0x3031_424e; // "NB10"
+// RSDS: signature (4) + GUID (16) + Age (4) precede the path.
+const CODEVIEW_RSDS_PATH_OFFSET: usize = 24;
+// NB10: signature (4) + Offset (4, always 0) + TimeDateStamp (4) + Age (4) precede the path.
+const CODEVIEW_NB10_PATH_OFFSET: usize = 16;
+
+// REF: MdePkg/Include/IndustryStandard/PeImage.h -- EFI_TE_IMAGE_HEADER
+//
+// A stripped-down header used by PEI-phase and some ARM firmware images in
+// place of the full DOS/PE/COFF header trio. It carries just enough of the
+// optional header and the first two data directories (base relocation and
+// debug) to load and relocate the image.
+struct TeImageHeader {
+  machine:                u16,
+  number_of_sections:     u8,
+  subsystem:               u8,
+  stripped_size:           u16,
+  address_of_entry_point:  u32,
+  base_of_code:            u32,
+  image_base:              u64,
+  base_relocation_directory: DataDirectory,
+  debug_directory:         DataDirectory,
+}
+
+#[derive(Clone,Copy,Debug,Default)]
+struct DataDirectory {
+  virtual_address: u32,
+  size:            u32,
+}
+
+impl TeImageHeader {
+  fn parse(buffer: &[u8]) -> Result<Self, PeCoffImageError> {
+    if buffer.len() < TE_IMAGE_HEADER_SIZE {
+      return Err(PeCoffImageError::ImageErrorImageRead);
     }
+
+    let signature = u16::from_le_bytes(buffer[0..2].try_into().unwrap());
+    if signature != TE_IMAGE_HEADER_SIGNATURE {
+      return Err(PeCoffImageError::ImageErrorInvalidPeHeaderSignature);
+    }
+
+    Ok(Self {
+      machine:               u16::from_le_bytes(buffer[2..4].try_into().unwrap()),
+      number_of_sections:    buffer[4],
+      subsystem:             buffer[5],
+      stripped_size:         u16::from_le_bytes(buffer[6..8].try_into().unwrap()),
+      address_of_entry_point: u32::from_le_bytes(buffer[8..12].try_into().unwrap()),
+      base_of_code:          u32::from_le_bytes(buffer[12..16].try_into().unwrap()),
+      image_base:            u64::from_le_bytes(buffer[16..24].try_into().unwrap()),
+      base_relocation_directory: DataDirectory {
+        virtual_address: u32::from_le_bytes(buffer[24..28].try_into().unwrap()),
+        size:            u32::from_le_bytes(buffer[28..32].try_into().unwrap()),
+      },
+      debug_directory: DataDirectory {
+        virtual_address: u32::from_le_bytes(buffer[32..36].try_into().unwrap()),
+        size:            u32::from_le_bytes(buffer[36..40].try_into().unwrap()),
+      },
+    })
+  }
+
+  // The offset every file pointer embedded in the (unmodified) section table
+  // and data directories needs to be corrected by, since the TE transform
+  // strips everything before BaseOfCode out of the original PE image.
+  fn stripped_offset(&self) -> u32 {
+    (self.stripped_size as u32).saturating_sub(TE_IMAGE_HEADER_SIZE as u32)
+  }
+}
+
+// REF: MdePkg/Include/IndustryStandard/PeImage.h --
+// EFI_IMAGE_OPTIONAL_HEADER32/EFI_IMAGE_OPTIONAL_HEADER64
+//
+// Only the fields update_info_from_headers() and sections_for_relocation()
+// actually need are pulled out; the PE32 and PE32+ forms share every field
+// offset up through SectionAlignment and diverge only in the width of
+// ImageBase (and, later, the stack/heap reserve/commit fields, which this
+// loader never reads).
+struct PeOptionalHeaderInfo {
+  size_of_headers:           u32,
+  section_alignment:         u32,
+  address_of_entry_point:    u32,
+  image_size:                u32,
+  subsystem:                 u16,
+  image_base:                u64,
+  base_relocation_directory: Option<DataDirectory>,
+  debug_directory:           Option<DataDirectory>,
+}
+
+impl PeOptionalHeaderInfo {
+  fn parse(buffer: &[u8]) -> Result<Self, PeCoffImageError> {
+    let field = |offset: usize, size: usize| -> Result<&[u8], PeCoffImageError> {
+      buffer.get(offset..offset + size).ok_or(PeCoffImageError::ImageErrorImageRead)
+    };
+
+    let magic = u16::from_le_bytes(field(0, 2)?.try_into().unwrap());
+    // (StandardFields size, sizeof ImageBase) -- PE32 carries an extra
+    // 4-byte BaseOfData field that PE32+ drops in favor of a wider ImageBase.
+    let (standard_fields_size, image_base_size) = match magic {
+      OPTIONAL_HEADER_MAGIC_PE32 => (28usize, 4usize),
+      OPTIONAL_HEADER_MAGIC_PE32_PLUS => (24usize, 8usize),
+      _ => return Err(PeCoffImageError::ImageErrorInvalidPeHeaderSignature),
+    };
+
+    // Magic (2) + linker version (2) + SizeOfCode/SizeOfInitializedData/
+    // SizeOfUninitializedData (4 each) precede AddressOfEntryPoint.
+    let address_of_entry_point = u32::from_le_bytes(field(16, 4)?.try_into().unwrap());
+
+    let image_base_offset = standard_fields_size;
+    let image_base = if image_base_size == 8 {
+      u64::from_le_bytes(field(image_base_offset, 8)?.try_into().unwrap())
+    } else {
+      u32::from_le_bytes(field(image_base_offset, 4)?.try_into().unwrap()) as u64
+    };
+
+    // SectionAlignment immediately follows ImageBase; FileAlignment (unused
+    // here) follows that.
+    let section_alignment_offset = image_base_offset + image_base_size;
+    let section_alignment = u32::from_le_bytes(field(section_alignment_offset, 4)?.try_into().unwrap());
+
+    // Six u16 version fields (MajorOperatingSystemVersion .. MinorSubsystemVersion)
+    // and one u32 (Win32VersionValue) separate FileAlignment from SizeOfImage.
+    let size_of_image_offset = section_alignment_offset + 8 + 6 * 2 + 4;
+    let image_size = u32::from_le_bytes(field(size_of_image_offset, 4)?.try_into().unwrap());
+    let size_of_headers = u32::from_le_bytes(field(size_of_image_offset + 4, 4)?.try_into().unwrap());
+    // CheckSum (u32) sits between SizeOfHeaders and Subsystem.
+    let subsystem = u16::from_le_bytes(field(size_of_image_offset + 12, 2)?.try_into().unwrap());
+
+    // DllCharacteristics (u16), the four stack/heap reserve/commit fields
+    // (each ImageBase-width), and LoaderFlags (u32) separate Subsystem from
+    // NumberOfRvaAndSizes.
+    let number_of_rva_and_sizes_offset = size_of_image_offset + 14 + 2 + 4 * image_base_size + 4;
+    let number_of_rva_and_sizes = u32::from_le_bytes(field(number_of_rva_and_sizes_offset, 4)?.try_into().unwrap());
+    let data_directories_offset = number_of_rva_and_sizes_offset + 4;
+
+    let data_directory = |index: usize| -> Option<DataDirectory> {
+      if index >= MAX_DATA_DIRECTORY_ENTRIES || index as u32 >= number_of_rva_and_sizes {
+        return None;
+      }
+      let entry = field(data_directories_offset + index * 8, 8).ok()?;
+      Some(DataDirectory {
+        virtual_address: u32::from_le_bytes(entry[0..4].try_into().unwrap()),
+        size:            u32::from_le_bytes(entry[4..8].try_into().unwrap()),
+      })
+    };
+
+    Ok(Self {
+      size_of_headers,
+      section_alignment,
+      address_of_entry_point,
+      image_size,
+      subsystem,
+      image_base,
+      base_relocation_directory: data_directory(IMAGE_DIRECTORY_ENTRY_BASERELOC).filter(|directory| directory.size > 0),
+      debug_directory:           data_directory(IMAGE_DIRECTORY_ENTRY_DEBUG).filter(|directory| directory.size > 0),
+    })
+  }
+}
+
+// The result of parse_pe_coff_header(): the fixed COFF fields plus the parsed
+// optional header, along with the file offset of the section table that
+// immediately follows it.
+struct PeCoffHeaderInfo {
+  machine:             u16,
+  characteristics:     u16,
+  number_of_sections:  u16,
+  section_table_offset: usize,
+  optional_header:     PeOptionalHeaderInfo,
+}
+
+// A minimal, format-agnostic view of a section header, shared by the PE and
+// TE (both hand-parsed) code paths so relocate_image/load_image don't need
+// to know which kind of image they're working with.
+#[derive(Clone,Copy,Debug)]
+struct RawSection {
+  name:                [u8; 8],
+  virtual_address:     u32,
+  virtual_size:        u32,
+  size_of_raw_data:    u32,
+  pointer_to_raw_data: u32,
+}
+
+impl RawSection {
+  fn parse(buffer: &[u8]) -> Result<Self, PeCoffImageError> {
+    if buffer.len() < IMAGE_SECTION_HEADER_SIZE {
+      return Err(PeCoffImageError::ImageErrorImageRead);
+    }
+
+    Ok(Self {
+      name:                buffer[0..8].try_into().unwrap(),
+      virtual_size:        u32::from_le_bytes(buffer[8..12].try_into().unwrap()),
+      virtual_address:     u32::from_le_bytes(buffer[12..16].try_into().unwrap()),
+      size_of_raw_data:    u32::from_le_bytes(buffer[16..20].try_into().unwrap()),
+      pointer_to_raw_data: u32::from_le_bytes(buffer[20..24].try_into().unwrap()),
+    })
+  }
+
+  // Section names are fixed 8-byte fields, NUL-padded (and, for a classic PE,
+  // possibly NUL-terminated early); compare against the padded form so a
+  // short name like ".text" matches regardless of trailing NULs.
+  fn name_matches(&self, name: &str) -> bool {
+    let mut expected = [0u8; 8];
+    let name_bytes = name.as_bytes();
+    if name_bytes.len() > expected.len() {
+      return false;
+    }
+    expected[..name_bytes.len()].copy_from_slice(name_bytes);
+    self.name == expected
+  }
 }
 
 // REF: MdePkg/Include/Library/PeCoffLib.h
@@ -216,26 +496,692 @@ impl PeCoffLoaderImageContext {
     Ok(buffer)
   }
 
+  // REF: MdePkg/Library/BasePeCoffLib/BasePeCoff.c -- PeCoffLoaderGetPeHeader
+  //
+  // Used at the staged, fixed-size header/section-table/section-data reads
+  // that parse or copy out trusted structure, where a short read must be
+  // treated as a failure rather than silently zero-padded -- unlike
+  // read_image_into(), which tolerates a short read for callers (like
+  // test_offset()) that are only probing how much is available.
+  fn read_exact_into(&self, offset: usize, buffer: &mut [u8]) -> Result<(), PeCoffImageError> {
+    if self.read_image_into(offset, buffer)? != buffer.len() {
+      return Err(PeCoffImageError::ImageErrorImageRead);
+    }
+    Ok(())
+  }
+
+  // REF: MdePkg/Library/BasePeCoffLib/BasePeCoff.c -- PeCoffLoaderGetPeHeader
+  //
+  // Reads only what's needed to validate and describe the image, in stages,
+  // through the PE_COFF_LOADER_READ_FILE callback: the DOS header, then the
+  // PE signature and COFF header, then the optional header, then the section
+  // table, then any debug directory entries (TE images skip straight from
+  // their fixed header to the section table). Nothing here ever loads the
+  // section contents as a whole, and every offset/count taken from the
+  // untrusted image is bounds-checked before it's used to size a read.
   pub fn update_info_from_headers(&mut self) -> Result<(), PeCoffImageError> {
-    // let dos_header_buffer = self.read_image(0, Self::DOS_HEADER_SIZE)?;
-    // let dos_header = goblin::pe::header::DosHeader::parse(&dos_header_buffer)?;
-    // if dos_header.signature != goblin::pe::header::DOS_MAGIC {
-    //   return Err(PeCoffImageError::ImageErrorImageRead);
-    // }
-    // self.pe_coff_header_offset = dos_header.pe_pointer;
+    let mut dos_header = [0u8; DOS_HEADER_SIZE];
+    self.read_exact_into(0, &mut dos_header)?;
+
+    let signature = u16::from_le_bytes(dos_header[0..2].try_into().unwrap());
+    if signature == TE_IMAGE_HEADER_SIGNATURE {
+      return self.update_info_from_te_header();
+    }
+
+    if signature != DOS_IMAGE_HEADER_SIGNATURE {
+      self.image_error = PeCoffImageError::ImageErrorInvalidPeHeaderSignature;
+      return Err(PeCoffImageError::ImageErrorInvalidPeHeaderSignature);
+    }
+
+    let pe_coff_header_offset = u32::from_le_bytes(
+      dos_header[DOS_HEADER_LFANEW_OFFSET..DOS_HEADER_LFANEW_OFFSET + 4].try_into().unwrap());
+
+    let header = match self.parse_pe_coff_header(pe_coff_header_offset) {
+      Ok(header) => header,
+      Err(err) => {
+        self.image_error = err;
+        return Err(err);
+      }
+    };
+
+    if !SUPPORTED_MACHINE_TYPES.contains(&header.machine) {
+      self.image_error = PeCoffImageError::ImageErrorInvalidMachineType;
+      return Err(PeCoffImageError::ImageErrorInvalidMachineType);
+    }
+
+    let subsystem = header.optional_header.subsystem;
+    if subsystem != IMAGE_SUBSYSTEM_EFI_APPLICATION &&
+       subsystem != IMAGE_SUBSYSTEM_EFI_BOOT_SERVICE_DRIVER &&
+       subsystem != IMAGE_SUBSYSTEM_EFI_RUNTIME_DRIVER {
+      self.image_error = PeCoffImageError::ImageErrorInvalidSubsystem;
+      return Err(PeCoffImageError::ImageErrorInvalidSubsystem);
+    }
+
+    // Relocations are considered stripped if the linker said so, or if there's
+    // simply nothing in the base relocation directory to apply.
+    let relocations_stripped = (header.characteristics & IMAGE_FILE_RELOCS_STRIPPED) != 0 ||
+      match header.optional_header.base_relocation_directory {
+        Some(base_relocation_directory) => base_relocation_directory.size == 0,
+        None => true,
+      };
+
+    let sections = match self.parse_pe_sections(&header) {
+      Ok(sections) => sections,
+      Err(err) => {
+        self.image_error = err;
+        return Err(err);
+      }
+    };
+
+    if let Err(err) = self.update_debug_directory_info(header.optional_header.debug_directory, &sections, 0,
+                                                       header.optional_header.image_size as u64) {
+      self.image_error = err;
+      return Err(err);
+    }
+
+    self.pe_coff_header_offset = pe_coff_header_offset;
+    self.machine = header.machine;
+    self.image_type = subsystem;
+    self.size_of_headers = header.optional_header.size_of_headers as usize;
+    self.section_alignment = header.optional_header.section_alignment;
+    self.entry_point = header.optional_header.address_of_entry_point as base::PhysicalAddress;
+    self.image_size = header.optional_header.image_size as u64;
+    self.relocations_stripped = if relocations_stripped { base::Boolean::TRUE } else { base::Boolean::FALSE };
+    self.is_te_image = base::Boolean::FALSE;
+
+    self.image_error = PeCoffImageError::ImageErrorSuccess;
+    Ok(())
+  }
+
+  // Reads the PE signature, COFF header, and optional header starting at
+  // `pe_coff_header_offset`, bounds-checking NumberOfSections and
+  // SizeOfOptionalHeader before they're used to size anything further.
+  fn parse_pe_coff_header(&self, pe_coff_header_offset: u32) -> Result<PeCoffHeaderInfo, PeCoffImageError> {
+    let mut pe_signature = [0u8; PE_SIGNATURE_SIZE];
+    self.read_exact_into(pe_coff_header_offset as usize, &mut pe_signature)?;
+    if u32::from_le_bytes(pe_signature) != PE_IMAGE_HEADER_SIGNATURE {
+      return Err(PeCoffImageError::ImageErrorInvalidPeHeaderSignature);
+    }
+
+    let coff_header_offset = pe_coff_header_offset as usize + PE_SIGNATURE_SIZE;
+    let mut coff_header = [0u8; COFF_HEADER_SIZE];
+    self.read_exact_into(coff_header_offset, &mut coff_header)?;
+
+    let machine = u16::from_le_bytes(coff_header[0..2].try_into().unwrap());
+    let number_of_sections = u16::from_le_bytes(coff_header[2..4].try_into().unwrap());
+    let size_of_optional_header = u16::from_le_bytes(coff_header[16..18].try_into().unwrap());
+    let characteristics = u16::from_le_bytes(coff_header[18..20].try_into().unwrap());
+
+    if number_of_sections as usize > MAX_SECTION_COUNT {
+      return Err(PeCoffImageError::ImageErrorInvalidImageSize);
+    }
+    if size_of_optional_header == 0 || size_of_optional_header as usize > MAX_OPTIONAL_HEADER_SIZE {
+      return Err(PeCoffImageError::ImageErrorInvalidPeHeaderSignature);
+    }
+
+    let optional_header_offset = coff_header_offset + COFF_HEADER_SIZE;
+    let mut optional_header_buffer = alloc::vec![0u8; size_of_optional_header as usize];
+    self.read_exact_into(optional_header_offset, &mut optional_header_buffer)?;
+    let optional_header = PeOptionalHeaderInfo::parse(&optional_header_buffer)?;
+
+    Ok(PeCoffHeaderInfo {
+      machine,
+      characteristics,
+      number_of_sections,
+      section_table_offset: optional_header_offset + size_of_optional_header as usize,
+      optional_header,
+    })
+  }
+
+  // Reads the section table described by an already-parsed PE/COFF header,
+  // one EFI_IMAGE_SECTION_HEADER at a time.
+  fn parse_pe_sections(&self, header: &PeCoffHeaderInfo) -> Result<Vec<RawSection>, PeCoffImageError> {
+    let mut sections = Vec::with_capacity(header.number_of_sections as usize);
+    let mut offset = header.section_table_offset;
+    for _ in 0..header.number_of_sections {
+      let mut section_buffer = [0u8; IMAGE_SECTION_HEADER_SIZE];
+      self.read_exact_into(offset, &mut section_buffer)?;
+      sections.push(RawSection::parse(&section_buffer)?);
+      offset += IMAGE_SECTION_HEADER_SIZE;
+    }
+    Ok(sections)
+  }
+
+  // REF: MdePkg/Include/IndustryStandard/PeImage.h -- EFI_TE_IMAGE_HEADER
+  //
+  // TE images omit the DOS stub, the PE signature, and most of the COFF/
+  // optional header, so they're parsed entirely by hand. The section table
+  // immediately follows the fixed-size header.
+  fn update_info_from_te_header(&mut self) -> Result<(), PeCoffImageError> {
+    let mut te_header_buffer = [0u8; TE_IMAGE_HEADER_SIZE];
+    self.read_exact_into(0, &mut te_header_buffer)?;
+    let te_header = TeImageHeader::parse(&te_header_buffer)?;
+
+    if !SUPPORTED_MACHINE_TYPES.contains(&te_header.machine) {
+      self.image_error = PeCoffImageError::ImageErrorInvalidMachineType;
+      return Err(PeCoffImageError::ImageErrorInvalidMachineType);
+    }
+
+    let subsystem = te_header.subsystem as u16;
+    if subsystem != IMAGE_SUBSYSTEM_EFI_APPLICATION &&
+       subsystem != IMAGE_SUBSYSTEM_EFI_BOOT_SERVICE_DRIVER &&
+       subsystem != IMAGE_SUBSYSTEM_EFI_RUNTIME_DRIVER {
+      self.image_error = PeCoffImageError::ImageErrorInvalidSubsystem;
+      return Err(PeCoffImageError::ImageErrorInvalidSubsystem);
+    }
+
+    if te_header.number_of_sections as usize > MAX_SECTION_COUNT {
+      self.image_error = PeCoffImageError::ImageErrorInvalidImageSize;
+      return Err(PeCoffImageError::ImageErrorInvalidImageSize);
+    }
+
+    let sections = self.parse_te_sections(&te_header)?;
+    let image_size = sections.iter()
+      .map(|section| section.virtual_address as u64 + core::cmp::max(section.virtual_size, section.size_of_raw_data) as u64)
+      .max()
+      .unwrap_or(0);
+
+    let debug_directory = if te_header.debug_directory.size > 0 { Some(te_header.debug_directory) } else { None };
+    self.update_debug_directory_info(debug_directory, &sections, te_header.stripped_offset(), image_size)?;
+
+    self.machine = te_header.machine;
+    self.image_type = subsystem;
+    self.entry_point = te_header.address_of_entry_point as base::PhysicalAddress;
+    self.section_alignment = 4;
+    self.size_of_headers = TE_IMAGE_HEADER_SIZE + te_header.base_of_code as usize;
+    self.image_size = image_size;
+    self.relocations_stripped = if te_header.base_relocation_directory.size == 0 { base::Boolean::TRUE } else { base::Boolean::FALSE };
+    self.is_te_image = base::Boolean::TRUE;
+
+    self.image_error = PeCoffImageError::ImageErrorSuccess;
+    Ok(())
+  }
+
+  fn parse_te_sections(&self, te_header: &TeImageHeader) -> Result<Vec<RawSection>, PeCoffImageError> {
+    let mut sections = Vec::with_capacity(te_header.number_of_sections as usize);
+    let mut offset = TE_IMAGE_HEADER_SIZE;
+    for _ in 0..te_header.number_of_sections {
+      let mut section_buffer = [0u8; IMAGE_SECTION_HEADER_SIZE];
+      self.read_exact_into(offset, &mut section_buffer)?;
+      sections.push(RawSection::parse(&section_buffer)?);
+      offset += IMAGE_SECTION_HEADER_SIZE;
+    }
+    Ok(sections)
+  }
+
+  // A format-agnostic view of what relocate_image()/load_image() need: the
+  // section table, the base relocation directory (if any), the image's
+  // preferred load address, and the stripped-header file offset correction
+  // (always 0 for a plain PE/COFF image). Re-derives everything from the
+  // already-validated header offsets rather than re-reading the image body.
+  fn sections_for_relocation(&self) -> Result<(Vec<RawSection>, Option<DataDirectory>, u64, u32), PeCoffImageError> {
+    if self.is_te_image == base::Boolean::TRUE {
+      let mut te_header_buffer = [0u8; TE_IMAGE_HEADER_SIZE];
+      self.read_exact_into(0, &mut te_header_buffer)?;
+      let te_header = TeImageHeader::parse(&te_header_buffer)?;
+      let sections = self.parse_te_sections(&te_header)?;
+      let base_relocation_directory = if te_header.base_relocation_directory.size > 0 {
+        Some(te_header.base_relocation_directory)
+      } else {
+        None
+      };
+      Ok((sections, base_relocation_directory, te_header.image_base, te_header.stripped_offset()))
+    }
+    else {
+      let header = self.parse_pe_coff_header(self.pe_coff_header_offset)?;
+      let sections = self.parse_pe_sections(&header)?;
+      Ok((sections, header.optional_header.base_relocation_directory, header.optional_header.image_base, 0))
+    }
+  }
+
+  // Shared by load_image() and section_data(): a section header pulled from
+  // an untrusted image can claim any virtual_address/virtual_size/
+  // size_of_raw_data, so both ends of the section must fit within
+  // `image_size` before either call site trusts them to size a read/copy.
+  fn section_fits_within(section: &RawSection, image_size: u64) -> bool {
+    let section_size = core::cmp::max(section.virtual_size, section.size_of_raw_data) as u64;
+    section.virtual_address as u64 + section_size <= image_size
+  }
+
+  // Find the file offset backing a given RVA by walking the section table,
+  // the same way EDK2's internal PeCoffLoaderImageAddress does.
+  //
+  // `file_offset_adjustment` corrects for a TE image's stripped header; it's
+  // always 0 for a plain PE/COFF image.
+  fn rva_to_file_offset(sections: &[RawSection], rva: u32, file_offset_adjustment: u32) -> Option<usize> {
+    for section in sections {
+      let section_size = core::cmp::max(section.virtual_size, section.size_of_raw_data);
+      let section_end = match section.virtual_address.checked_add(section_size) {
+        Some(section_end) => section_end,
+        None => continue,
+      };
+      if rva >= section.virtual_address && rva < section_end {
+        let section_offset = rva - section.virtual_address;
+        if (section_offset as u64) < section.size_of_raw_data as u64 {
+          let file_offset = section.pointer_to_raw_data.saturating_sub(file_offset_adjustment)
+            .checked_add(section_offset)?;
+          return Some(file_offset as usize);
+        }
+      }
+    }
+    None
+  }
+
+  // Locates the CodeView entry in the image's debug data directory (if any)
+  // and records its RVA in debug_directory_entry_rva. Unlike the other
+  // fields derived from the headers, debug info is purely optional: an
+  // absent debug directory, no CodeView entry in it, or a CodeView record
+  // with an unrecognized signature all just leave debug_directory_entry_rva
+  // at its zero default rather than failing the parse.
+  //
+  // This only reads from the source file through the PE_COFF_LOADER_READ_FILE
+  // callback -- the image isn't mapped to image_address/destination_address
+  // yet at header-parse time, so code_view/pdb_pointer aren't resolved here.
+  // That happens in resolve_debug_directory_pointers(), once load_image()
+  // has the image live in memory.
+  fn update_debug_directory_info(&mut self,
+                                  debug_directory: Option<DataDirectory>,
+                                  sections: &[RawSection],
+                                  file_offset_adjustment: u32,
+                                  image_size: u64) -> Result<(), PeCoffImageError> {
+    self.debug_directory_entry_rva = 0;
+    // A context can be reused across images; reset these here too so a
+    // failed or not-yet-attempted load_image() for this image can't leave a
+    // prior image's resolved pointers behind (resolve_debug_directory_pointers()
+    // re-derives them from scratch once load_image() actually succeeds).
+    self.code_view = core::ptr::null();
+    self.pdb_pointer = core::ptr::null();
+
+    let debug_directory = match debug_directory {
+      Some(debug_directory) => debug_directory,
+      None => return Ok(()),
+    };
+
+    let entry_table_offset = match Self::rva_to_file_offset(sections, debug_directory.virtual_address, file_offset_adjustment) {
+      Some(offset) => offset,
+      None => return Ok(()),
+    };
+
+    // debug_directory.size is an attacker-controlled u32 straight out of the
+    // data directory / TE header, so it must be checked against the image
+    // before it's trusted to size the entry_count loop below. `image_size` is
+    // the size just parsed from this image's headers; self.image_size isn't
+    // updated until the caller's parse has succeeded.
+    if debug_directory.size as u64 > image_size {
+      return Ok(());
+    }
+
+    let entry_count = debug_directory.size as usize / IMAGE_DEBUG_DIRECTORY_ENTRY_SIZE;
+    for index in 0..entry_count {
+      let mut entry_buffer = [0u8; IMAGE_DEBUG_DIRECTORY_ENTRY_SIZE];
+      self.read_exact_into(entry_table_offset + index * IMAGE_DEBUG_DIRECTORY_ENTRY_SIZE, &mut entry_buffer)?;
+
+      let entry_type = u32::from_le_bytes(entry_buffer[12..16].try_into().unwrap());
+      if entry_type != IMAGE_DEBUG_TYPE_CODEVIEW {
+        continue;
+      }
+
+      let code_view_rva = u32::from_le_bytes(entry_buffer[20..24].try_into().unwrap());
+      let code_view_offset = match Self::rva_to_file_offset(sections, code_view_rva, file_offset_adjustment) {
+        Some(offset) => offset,
+        None => return Ok(()),
+      };
+
+      let mut signature_buffer = [0u8; 4];
+      self.read_exact_into(code_view_offset, &mut signature_buffer)?;
+      if !matches!(u32::from_le_bytes(signature_buffer), CODEVIEW_SIGNATURE_RSDS | CODEVIEW_SIGNATURE_NB10) {
+        return Ok(());
+      }
+
+      self.debug_directory_entry_rva = debug_directory.virtual_address + (index * IMAGE_DEBUG_DIRECTORY_ENTRY_SIZE) as u32;
+      return Ok(());
+    }
+
+    Ok(())
+  }
+
+  // REF: MdePkg/Library/BasePeCoffLib/BasePeCoff.c -- PeCoffLoaderRelocateImage
+  //
+  // Resolves code_view/pdb_pointer from debug_directory_entry_rva, now that
+  // the image is mapped at image_address. Unlike update_debug_directory_info,
+  // this reads straight out of the loaded image rather than through the
+  // PE_COFF_LOADER_READ_FILE callback, since that's the only copy of the
+  // image left once the caller's file handle may no longer be valid.
+  fn resolve_debug_directory_pointers(&mut self) {
+    self.code_view = core::ptr::null();
+    self.pdb_pointer = core::ptr::null();
+
+    if self.debug_directory_entry_rva == 0 {
+      return;
+    }
+
+    // debug_directory_entry_rva/code_view_rva are only validated at
+    // header-parse time against the file's section layout, not against the
+    // final image_size -- check both reads fit before dereferencing them.
+    if (self.debug_directory_entry_rva as u64) + IMAGE_DEBUG_DIRECTORY_ENTRY_SIZE as u64 > self.image_size {
+      return;
+    }
+
+    unsafe {
+      let entry_address = self.image_address + self.debug_directory_entry_rva as u64;
+      let code_view_rva = core::ptr::read_unaligned((entry_address + 20) as *const u32);
+      if code_view_rva == 0 {
+        return;
+      }
+
+      if (code_view_rva as u64) + 4 > self.image_size {
+        return;
+      }
+
+      let code_view = (self.image_address + code_view_rva as u64) as *const core::ffi::c_void;
+      let signature = core::ptr::read_unaligned(code_view as *const u32);
+      let path_offset = match signature {
+        CODEVIEW_SIGNATURE_RSDS => CODEVIEW_RSDS_PATH_OFFSET,
+        CODEVIEW_SIGNATURE_NB10 => CODEVIEW_NB10_PATH_OFFSET,
+        _ => return,
+      };
+
+      if (code_view_rva as u64) + path_offset as u64 > self.image_size {
+        return;
+      }
+
+      self.code_view = code_view;
+      self.pdb_pointer = (code_view as u64 + path_offset as u64) as *const u8;
+    }
+  }
+
+  // Reads the NUL-terminated PDB path out of the CodeView record located at
+  // `pdb_pointer`, for tooling that wants to symbolicate this image or emit
+  // a GDB/WinDbg "add symbol file" command for it at its runtime
+  // image_address. Returns ImageErrorUnsupported if the image has no
+  // CodeView debug info, or if load_image() hasn't run yet to resolve it
+  // (see resolve_debug_directory_pointers).
+  pub fn pdb_path(&self) -> Result<&str, PeCoffImageError> {
+    if self.pdb_pointer.is_null() {
+      return Err(PeCoffImageError::ImageErrorUnsupported);
+    }
+
+    // resolve_debug_directory_pointers() bounds-checks pdb_pointer against
+    // image_size before setting it, but cap the NUL scan here too so a
+    // CodeView record with no NUL before the end of the image can't walk
+    // this off the end of it looking for one.
+    let pdb_offset = (self.pdb_pointer as u64).wrapping_sub(self.image_address);
+    if pdb_offset >= self.image_size {
+      return Err(PeCoffImageError::ImageErrorUnsupported);
+    }
+    let max_len = (self.image_size - pdb_offset) as usize;
+
+    unsafe {
+      let mut len = 0usize;
+      while len < max_len && *self.pdb_pointer.add(len) != 0 {
+        len += 1;
+      }
+      if len == max_len {
+        return Err(PeCoffImageError::ImageErrorUnsupported);
+      }
+      let bytes = core::slice::from_raw_parts(self.pdb_pointer, len);
+      core::str::from_utf8(bytes).map_err(|_| PeCoffImageError::ImageErrorUnsupported)
+    }
+  }
+
+  // REF: MdePkg/Library/BasePeCoffLib/BasePeCoff.c -- PeCoffLoaderRelocateImage
+  //
+  // Walks the base relocation directory and rebases every fixup recorded in
+  // it by the delta between the image's preferred base and where it actually
+  // ended up in memory.
+  pub fn relocate_image(&mut self) -> Result<(), PeCoffImageError> {
+    let (sections, base_relocation_directory, original_image_base, file_offset_adjustment) =
+      self.sections_for_relocation()?;
+
+    let delta = self.destination_address.wrapping_sub(original_image_base);
+
+    if delta == 0 {
+      // Already loaded at its preferred base; nothing to fix up.
+      self.image_error = PeCoffImageError::ImageErrorSuccess;
+      return Ok(());
+    }
+
+    if self.relocations_stripped == base::Boolean::TRUE {
+      self.image_error = PeCoffImageError::ImageErrorFailedRelocation;
+      return Err(PeCoffImageError::ImageErrorFailedRelocation);
+    }
+
+    let base_relocation_table = match base_relocation_directory {
+      Some(base_relocation_table) => base_relocation_table,
+      None => {
+        self.image_error = PeCoffImageError::ImageErrorFailedRelocation;
+        return Err(PeCoffImageError::ImageErrorFailedRelocation);
+      }
+    };
+
+    let reloc_start = match Self::rva_to_file_offset(&sections, base_relocation_table.virtual_address, file_offset_adjustment) {
+      Some(offset) => offset,
+      None => {
+        self.image_error = PeCoffImageError::ImageErrorFailedRelocation;
+        return Err(PeCoffImageError::ImageErrorFailedRelocation);
+      }
+    };
+    // base_relocation_table.size comes straight from the optional header's
+    // data directory -- an attacker-controlled u32 -- so it must be checked
+    // against the image before it's trusted to size the read below.
+    if base_relocation_table.size as u64 > self.image_size {
+      self.image_error = PeCoffImageError::ImageErrorFailedRelocation;
+      return Err(PeCoffImageError::ImageErrorFailedRelocation);
+    }
+
+    // Only the relocation directory itself is read, rather than the whole
+    // image, since that's all this loop ever touches.
+    let relocation_data = self.read_image(reloc_start, base_relocation_table.size as usize)?;
+
+    let mut block_offset = 0;
+    while block_offset + 8 <= relocation_data.len() {
+      let page_rva = u32::from_le_bytes(relocation_data[block_offset..block_offset + 4].try_into().unwrap());
+      let size_of_block = u32::from_le_bytes(relocation_data[block_offset + 4..block_offset + 8].try_into().unwrap());
+      if size_of_block < 8 || block_offset + size_of_block as usize > relocation_data.len() {
+        self.image_error = PeCoffImageError::ImageErrorFailedRelocation;
+        return Err(PeCoffImageError::ImageErrorFailedRelocation);
+      }
+
+      let entry_count = (size_of_block as usize - 8) / 2;
+      let mut entry_index = 0;
+      while entry_index < entry_count {
+        let entry_offset = block_offset + 8 + entry_index * 2;
+        let entry = u16::from_le_bytes(relocation_data[entry_offset..entry_offset + 2].try_into().unwrap());
+        let reloc_type = entry >> 12;
+        let page_offset = (entry & 0x0fff) as u32;
+
+        // page_rva and page_offset are both untrusted; reject anything that
+        // doesn't land within the image rather than let it overflow or walk
+        // off the end of the destination buffer. The width check below
+        // covers the other end of the write: a fixup type's full width must
+        // also fit inside the image, not just its starting byte.
+        let fixup_rva = match page_rva.checked_add(page_offset) {
+          Some(fixup_rva) if (fixup_rva as u64) < self.image_size => fixup_rva,
+          _ => {
+            self.image_error = PeCoffImageError::ImageErrorFailedRelocation;
+            return Err(PeCoffImageError::ImageErrorFailedRelocation);
+          }
+        };
+
+        let fixup_width: u64 = match reloc_type {
+          0 => 0, // EFI_IMAGE_REL_BASED_ABSOLUTE -- padding entry, no write.
+          1 | 2 => 2, // EFI_IMAGE_REL_BASED_HIGH / EFI_IMAGE_REL_BASED_LOW
+          3 => 4, // EFI_IMAGE_REL_BASED_HIGHLOW
+          4 => 2, // EFI_IMAGE_REL_BASED_HIGHADJ
+          10 => 8, // EFI_IMAGE_REL_BASED_DIR64
+          _ => {
+            self.image_error = PeCoffImageError::ImageErrorFailedRelocation;
+            return Err(PeCoffImageError::ImageErrorFailedRelocation);
+          }
+        };
+        if fixup_rva as u64 + fixup_width > self.image_size {
+          self.image_error = PeCoffImageError::ImageErrorFailedRelocation;
+          return Err(PeCoffImageError::ImageErrorFailedRelocation);
+        }
+
+        let target_address = self.destination_address + fixup_rva as u64;
+
+        match reloc_type {
+          0 => { /* EFI_IMAGE_REL_BASED_ABSOLUTE -- padding entry, skip. */ },
+          3 => unsafe { // EFI_IMAGE_REL_BASED_HIGHLOW
+            let fixup = target_address as *mut u32;
+            let value = core::ptr::read_unaligned(fixup);
+            core::ptr::write_unaligned(fixup, value.wrapping_add(delta as u32));
+          },
+          10 => unsafe { // EFI_IMAGE_REL_BASED_DIR64
+            let fixup = target_address as *mut u64;
+            let value = core::ptr::read_unaligned(fixup);
+            core::ptr::write_unaligned(fixup, value.wrapping_add(delta));
+          },
+          1 => unsafe { // EFI_IMAGE_REL_BASED_HIGH
+            let fixup = target_address as *mut u16;
+            let value = core::ptr::read_unaligned(fixup) as u32;
+            let adjusted = value.wrapping_add((delta >> 16) as u32);
+            core::ptr::write_unaligned(fixup, adjusted as u16);
+          },
+          2 => unsafe { // EFI_IMAGE_REL_BASED_LOW
+            let fixup = target_address as *mut u16;
+            let value = core::ptr::read_unaligned(fixup) as u32;
+            let adjusted = value.wrapping_add(delta as u32 & 0xffff);
+            core::ptr::write_unaligned(fixup, adjusted as u16);
+          },
+          4 => unsafe { // EFI_IMAGE_REL_BASED_HIGHADJ -- consumes the next entry too.
+            entry_index += 1;
+            if entry_index >= entry_count {
+              self.image_error = PeCoffImageError::ImageErrorFailedRelocation;
+              return Err(PeCoffImageError::ImageErrorFailedRelocation);
+            }
+            let adj_offset = block_offset + 8 + entry_index * 2;
+            let adj_entry = u16::from_le_bytes(relocation_data[adj_offset..adj_offset + 2].try_into().unwrap());
+
+            let fixup = target_address as *mut u16;
+            let value = core::ptr::read_unaligned(fixup) as u32;
+            let combined = (value << 16) | (adj_entry as u32);
+            let adjusted = combined.wrapping_add(delta as u32);
+            core::ptr::write_unaligned(fixup, (adjusted >> 16) as u16);
+          },
+          _ => {
+            self.image_error = PeCoffImageError::ImageErrorFailedRelocation;
+            return Err(PeCoffImageError::ImageErrorFailedRelocation);
+          }
+        }
+
+        entry_index += 1;
+      }
+
+      block_offset += size_of_block as usize;
+    }
+
+    self.image_error = PeCoffImageError::ImageErrorSuccess;
+    Ok(())
+  }
+
+  // REF: MdePkg/Library/BasePeCoffLib/BasePeCoff.c -- PeCoffLoaderLoadImage
+  //
+  // Copies the headers and every section from the source image into
+  // `destination_address`, zero-filling the uninitialized tail of each
+  // section (e.g. .bss) that isn't backed by raw file data.
+  pub fn load_image(&mut self) -> Result<(), PeCoffImageError> {
+    if (self.size_of_headers as u64) > self.image_size {
+      self.image_error = PeCoffImageError::ImageErrorInvalidImageSize;
+      return Err(PeCoffImageError::ImageErrorInvalidImageSize);
+    }
+
+    // The image now lives at destination_address; resolve_debug_directory_pointers()
+    // (called once this copy is done) dereferences image_address expecting it
+    // to be where the image actually landed, so record that here rather than
+    // leaving it to the caller to keep the two fields in sync.
+    self.image_address = self.destination_address;
+
+    let mut header_buffer = alloc::vec![0u8; self.size_of_headers];
+    self.read_exact_into(0, &mut header_buffer)?;
+    unsafe {
+      core::ptr::copy_nonoverlapping(header_buffer.as_ptr(), self.destination_address as *mut u8, header_buffer.len());
+    }
 
-    // let optional_header_buffer = self.read_image(self.pe_coff_header_offset as usize, Self::OPTIONAL_HEADER_UNION_SIZE)?;
+    let (sections, _base_relocation_directory, _original_image_base, file_offset_adjustment) =
+      self.sections_for_relocation()?;
 
-    // SURE,
-    // That's one way to do it, and maybe the most efficient way.
-    // But we're here to do things easily, not efficiently.
-    let file_data = self.read_image(0, self.image_size as usize)?;
-    let pe_metadata = goblin::pe::PE::parse(&file_data)?;
+    for section in &sections {
+      if self.section_alignment != 0 && section.virtual_address % self.section_alignment != 0 {
+        self.image_error = PeCoffImageError::ImageErrorInvalidSectionAlignment;
+        return Err(PeCoffImageError::ImageErrorInvalidSectionAlignment);
+      }
+
+      if !Self::section_fits_within(section, self.image_size) {
+        self.image_error = PeCoffImageError::ImageErrorInvalidImageSize;
+        return Err(PeCoffImageError::ImageErrorInvalidImageSize);
+      }
+
+      let destination = self.destination_address + section.virtual_address as u64;
+      let raw_size = section.size_of_raw_data as usize;
+      let raw_data_offset = section.pointer_to_raw_data.saturating_sub(file_offset_adjustment) as usize;
+
+      if raw_size > 0 {
+        let mut section_buffer = alloc::vec![0u8; raw_size];
+        if self.read_exact_into(raw_data_offset, &mut section_buffer).is_err() {
+          self.image_error = PeCoffImageError::ImageErrorSectionNotLoaded;
+          return Err(PeCoffImageError::ImageErrorSectionNotLoaded);
+        }
+        unsafe {
+          core::ptr::copy_nonoverlapping(section_buffer.as_ptr(), destination as *mut u8, raw_size);
+        }
+      }
+
+      let virtual_size = section.virtual_size as usize;
+      if virtual_size > raw_size {
+        unsafe {
+          core::ptr::write_bytes((destination as *mut u8).add(raw_size), 0, virtual_size - raw_size);
+        }
+      }
+    }
+
+    self.resolve_debug_directory_pointers();
 
-    println!("{:?}", pe_metadata);
+    self.image_error = PeCoffImageError::ImageErrorSuccess;
+    Ok(())
+  }
+
+  // Stub loaders (unified-kernel / secure-boot style) embed their command
+  // line, kernel, and measurement data in custom sections -- e.g. ".cmdline",
+  // ".linux", ".osrel" -- rather than in a standard data directory. Locate
+  // one of those by name and return its raw, unparsed bytes.
+  pub fn section_data(&self, name: &str) -> Result<Vec<u8>, PeCoffImageError> {
+    let (sections, _base_relocation_directory, _original_image_base, file_offset_adjustment) =
+      self.sections_for_relocation()?;
 
-    self.image_error = PeCoffImageError::ImageErrorUnsupported;
-    Err(PeCoffImageError::ImageErrorUnsupported)
+    let section = sections.iter()
+      .find(|section| section.name_matches(name))
+      .ok_or(PeCoffImageError::ImageErrorSectionNotLoaded)?;
+
+    // A section header pulled from an untrusted stub-loader image can claim
+    // any virtual_address/size_of_raw_data, so both must fit within
+    // image_size before they're trusted to size a read -- the same check
+    // load_image() applies to every section before copying it.
+    if !Self::section_fits_within(section, self.image_size) {
+      return Err(PeCoffImageError::ImageErrorInvalidImageSize);
+    }
+
+    let offset = section.pointer_to_raw_data.saturating_sub(file_offset_adjustment) as usize;
+    self.read_image(offset, section.size_of_raw_data as usize)
+  }
+
+  // Convenience wrapper for sections like ".cmdline" that carry a plain
+  // UTF-8 string (EDK2 stub loaders typically NUL-terminate these; trailing
+  // NULs are trimmed so callers don't have to).
+  pub fn section_data_as_string(&self, name: &str) -> Result<alloc::string::String, PeCoffImageError> {
+    let mut data = self.section_data(name)?;
+    while data.last() == Some(&0) {
+      data.pop();
+    }
+    alloc::string::String::from_utf8(data).map_err(|_| PeCoffImageError::ImageErrorSectionNotLoaded)
+  }
+
+  // Convenience wrapper for sections like a Blake3/SHA digest that carry a
+  // fixed-size binary blob.
+  pub fn section_data_as_array<const SIZE: usize>(&self, name: &str) -> Result<[u8; SIZE], PeCoffImageError> {
+    let data = self.section_data(name)?;
+    data.try_into().map_err(|_| PeCoffImageError::ImageErrorSectionNotLoaded)
   }
 }
 
@@ -389,4 +1335,691 @@ mod ffi_context_tests {
     image_context.handle = &"RngDxe.efi" as *const &str as *const core::ffi::c_void;
     assert!(image_context.update_info_from_headers().is_ok());
   }
+
+  // A third reader, alongside test_mocked_reader and test_file_reader: this
+  // one serves bytes out of an in-memory buffer so a test can hand-build a
+  // minimal synthetic image (header, sections, relocation/debug directory
+  // bytes) without needing a real binary fixture on disk.
+  static mut SYNTHETIC_IMAGE: Vec<u8> = Vec::new();
+  extern "win64" fn test_synthetic_reader(
+      _file_handle: *const core::ffi::c_void,
+      file_offset: usize,
+      read_size: *mut usize,
+      output_buffer: *mut core::ffi::c_void
+      ) -> efi::Status {
+    unsafe {
+      if file_offset > SYNTHETIC_IMAGE.len() {
+        return efi::Status::INVALID_PARAMETER;
+      }
+      let available = SYNTHETIC_IMAGE.len() - file_offset;
+      let got = core::cmp::min(*read_size, available);
+      let destination = slice::from_raw_parts_mut(output_buffer as *mut u8, got);
+      destination.copy_from_slice(&SYNTHETIC_IMAGE[file_offset..file_offset + got]);
+      *read_size = got;
+    }
+    efi::Status::SUCCESS
+  }
+
+  // Builds a minimal, well-formed PE32+ image covering only the pieces this
+  // loader reads: DOS/PE/COFF/optional headers, a `.text` section holding
+  // `text_section`, and a second section (RVA 0x2000) named
+  // `data_section_name` holding `data_section` -- used to embed base
+  // relocation directory bytes, a custom stub-loader section, etc. without
+  // needing a real binary fixture.
+  fn build_synthetic_pe_image(image_base: u64,
+                               base_relocation_directory: (u32, u32),
+                               debug_directory: (u32, u32),
+                               text_section: &[u8],
+                               data_section_name: &[u8; 8],
+                               data_section: &[u8]) -> Vec<u8> {
+    const TEXT_RVA: u32 = 0x1000;
+    const DATA_RVA: u32 = 0x2000;
+    const SECTION_ALIGNMENT: u32 = 0x1000;
+    const TEXT_FILE_OFFSET: usize = 0x400;
+
+    let text_size = SECTION_ALIGNMENT as usize;
+    let data_file_offset = TEXT_FILE_OFFSET + text_size;
+    let image_size = DATA_RVA + SECTION_ALIGNMENT;
+
+    let mut optional_header = Vec::new();
+    optional_header.extend_from_slice(&OPTIONAL_HEADER_MAGIC_PE32_PLUS.to_le_bytes());
+    optional_header.extend_from_slice(&[0u8; 2]); // linker version
+    optional_header.extend_from_slice(&(text_size as u32).to_le_bytes()); // SizeOfCode
+    optional_header.extend_from_slice(&[0u8; 8]); // SizeOfInitializedData, SizeOfUninitializedData
+    optional_header.extend_from_slice(&TEXT_RVA.to_le_bytes()); // AddressOfEntryPoint
+    optional_header.extend_from_slice(&TEXT_RVA.to_le_bytes()); // BaseOfCode
+    optional_header.extend_from_slice(&image_base.to_le_bytes());
+    optional_header.extend_from_slice(&SECTION_ALIGNMENT.to_le_bytes());
+    optional_header.extend_from_slice(&0x200u32.to_le_bytes()); // FileAlignment
+    optional_header.extend_from_slice(&[0u8; 12]); // 6 x u16 OS/image/subsystem versions
+    optional_header.extend_from_slice(&[0u8; 4]); // Win32VersionValue
+    optional_header.extend_from_slice(&image_size.to_le_bytes());
+    optional_header.extend_from_slice(&(TEXT_FILE_OFFSET as u32).to_le_bytes()); // SizeOfHeaders
+    optional_header.extend_from_slice(&[0u8; 4]); // CheckSum
+    optional_header.extend_from_slice(&IMAGE_SUBSYSTEM_EFI_APPLICATION.to_le_bytes());
+    optional_header.extend_from_slice(&[0u8; 2]); // DllCharacteristics
+    optional_header.extend_from_slice(&[0u8; 32]); // 4 x u64 stack/heap reserve/commit
+    optional_header.extend_from_slice(&[0u8; 4]); // LoaderFlags
+    optional_header.extend_from_slice(&7u32.to_le_bytes()); // NumberOfRvaAndSizes
+    for index in 0..7u32 {
+      let (virtual_address, size) = if index == IMAGE_DIRECTORY_ENTRY_BASERELOC as u32 {
+        base_relocation_directory
+      } else if index == IMAGE_DIRECTORY_ENTRY_DEBUG as u32 {
+        debug_directory
+      } else {
+        (0, 0)
+      };
+      optional_header.extend_from_slice(&virtual_address.to_le_bytes());
+      optional_header.extend_from_slice(&size.to_le_bytes());
+    }
+
+    let section_header = |name: &[u8; 8], virtual_address: u32, size_of_raw_data: u32, pointer_to_raw_data: u32| -> Vec<u8> {
+      let mut header = name.to_vec();
+      header.extend_from_slice(&SECTION_ALIGNMENT.to_le_bytes()); // VirtualSize
+      header.extend_from_slice(&virtual_address.to_le_bytes());
+      header.extend_from_slice(&size_of_raw_data.to_le_bytes());
+      header.extend_from_slice(&pointer_to_raw_data.to_le_bytes());
+      header.extend_from_slice(&[0u8; 12]); // relocation/linenumber pointers+counts
+      header.extend_from_slice(&[0u8; 4]); // Characteristics
+      header
+    };
+
+    let mut image = vec![0u8; DOS_HEADER_SIZE];
+    image[0..2].copy_from_slice(b"MZ");
+    image[DOS_HEADER_LFANEW_OFFSET..DOS_HEADER_LFANEW_OFFSET + 4].copy_from_slice(&(DOS_HEADER_SIZE as u32).to_le_bytes());
+    image.extend_from_slice(b"PE\0\0");
+    image.extend_from_slice(&IMAGE_FILE_MACHINE_X64.to_le_bytes());
+    image.extend_from_slice(&2u16.to_le_bytes()); // NumberOfSections
+    image.extend_from_slice(&[0u8; 8]); // TimeDateStamp, PointerToSymbolTable
+    image.extend_from_slice(&[0u8; 4]); // NumberOfSymbols
+    image.extend_from_slice(&(optional_header.len() as u16).to_le_bytes());
+    image.extend_from_slice(&[0u8; 2]); // Characteristics
+    image.extend_from_slice(&optional_header);
+    image.extend_from_slice(&section_header(b".text\0\0\0", TEXT_RVA, text_size as u32, TEXT_FILE_OFFSET as u32));
+    image.extend_from_slice(&section_header(data_section_name, DATA_RVA, data_section.len() as u32, data_file_offset as u32));
+
+    image.resize(TEXT_FILE_OFFSET, 0);
+    image.extend_from_slice(text_section);
+    image.resize(data_file_offset, 0);
+    image.extend_from_slice(data_section);
+    image.resize(data_file_offset + SECTION_ALIGNMENT as usize, 0);
+
+    image
+  }
+
+  // update_info_from_headers() must reject a DOS header whose e_magic isn't
+  // "MZ" (and isn't the TE signature either).
+  #[test]
+  fn update_info_from_headers_rejects_a_bad_dos_signature() {
+    let mut image = build_synthetic_pe_image(0, (0, 0), (0, 0), &[0u8; 0x1000], b"junkjunk", &[]);
+    image[0..2].copy_from_slice(&0xffffu16.to_le_bytes());
+    unsafe { SYNTHETIC_IMAGE = image; }
+
+    let mut image_context = PeCoffLoaderImageContext::new(test_synthetic_reader);
+    assert_eq!(image_context.update_info_from_headers(), Err(PeCoffImageError::ImageErrorInvalidPeHeaderSignature));
+  }
+
+  // update_info_from_headers() must reject a machine type this loader doesn't
+  // know how to load/relocate, rather than trust it to "probably work".
+  #[test]
+  fn update_info_from_headers_rejects_an_unsupported_machine_type() {
+    let mut image = build_synthetic_pe_image(0, (0, 0), (0, 0), &[0u8; 0x1000], b"junkjunk", &[]);
+    let machine_offset = DOS_HEADER_SIZE + PE_SIGNATURE_SIZE;
+    image[machine_offset..machine_offset + 2].copy_from_slice(&0x1234u16.to_le_bytes());
+    unsafe { SYNTHETIC_IMAGE = image; }
+
+    let mut image_context = PeCoffLoaderImageContext::new(test_synthetic_reader);
+    assert_eq!(image_context.update_info_from_headers(), Err(PeCoffImageError::ImageErrorInvalidMachineType));
+  }
+
+  // update_info_from_headers() must reject a subsystem other than the three
+  // UEFI ones this loader supports.
+  #[test]
+  fn update_info_from_headers_rejects_an_unsupported_subsystem() {
+    let mut image = build_synthetic_pe_image(0, (0, 0), (0, 0), &[0u8; 0x1000], b"junkjunk", &[]);
+    let coff_header_offset = DOS_HEADER_SIZE + PE_SIGNATURE_SIZE;
+    let optional_header_offset = coff_header_offset + COFF_HEADER_SIZE;
+    // Subsystem sits 68 bytes into this synthetic PE32+ optional header:
+    // Magic/linker(4) + SizeOfCode/Data x3(12) + EntryPoint/BaseOfCode(8) +
+    // ImageBase(8) + SectionAlignment/FileAlignment(8) + versions(12) +
+    // Win32VersionValue(4) + SizeOfImage/SizeOfHeaders(8) + CheckSum(4).
+    let subsystem_offset = optional_header_offset + 68;
+    image[subsystem_offset..subsystem_offset + 2].copy_from_slice(&0xffffu16.to_le_bytes());
+    unsafe { SYNTHETIC_IMAGE = image; }
+
+    let mut image_context = PeCoffLoaderImageContext::new(test_synthetic_reader);
+    assert_eq!(image_context.update_info_from_headers(), Err(PeCoffImageError::ImageErrorInvalidSubsystem));
+  }
+
+  // parse_pe_coff_header() must reject a corrupted "PE\0\0" signature rather
+  // than trust the bytes that follow it.
+  #[test]
+  fn parse_pe_coff_header_rejects_a_bad_pe_signature() {
+    let mut image = build_synthetic_pe_image(0, (0, 0), (0, 0), &[0u8; 0x1000], b"junkjunk", &[]);
+    image[DOS_HEADER_SIZE..DOS_HEADER_SIZE + PE_SIGNATURE_SIZE].copy_from_slice(b"XXXX");
+    unsafe { SYNTHETIC_IMAGE = image; }
+
+    let mut image_context = PeCoffLoaderImageContext::new(test_synthetic_reader);
+    assert_eq!(image_context.update_info_from_headers(), Err(PeCoffImageError::ImageErrorInvalidPeHeaderSignature));
+  }
+
+  // parse_pe_coff_header() must reject a NumberOfSections beyond
+  // MAX_SECTION_COUNT before it's ever used to size the section table walk.
+  #[test]
+  fn parse_pe_coff_header_rejects_an_oversized_section_count() {
+    let mut image = build_synthetic_pe_image(0, (0, 0), (0, 0), &[0u8; 0x1000], b"junkjunk", &[]);
+    let number_of_sections_offset = DOS_HEADER_SIZE + PE_SIGNATURE_SIZE + 2;
+    image[number_of_sections_offset..number_of_sections_offset + 2]
+      .copy_from_slice(&((MAX_SECTION_COUNT + 1) as u16).to_le_bytes());
+    unsafe { SYNTHETIC_IMAGE = image; }
+
+    let mut image_context = PeCoffLoaderImageContext::new(test_synthetic_reader);
+    assert_eq!(image_context.update_info_from_headers(), Err(PeCoffImageError::ImageErrorInvalidImageSize));
+  }
+
+  // PeOptionalHeaderInfo::parse() must reject a magic other than the PE32/
+  // PE32+ optional header magics it knows how to interpret.
+  #[test]
+  fn parse_pe_coff_header_rejects_a_bad_optional_header_magic() {
+    let mut image = build_synthetic_pe_image(0, (0, 0), (0, 0), &[0u8; 0x1000], b"junkjunk", &[]);
+    let coff_header_offset = DOS_HEADER_SIZE + PE_SIGNATURE_SIZE;
+    let optional_header_offset = coff_header_offset + COFF_HEADER_SIZE;
+    image[optional_header_offset..optional_header_offset + 2].copy_from_slice(&0xffffu16.to_le_bytes());
+    unsafe { SYNTHETIC_IMAGE = image; }
+
+    let mut image_context = PeCoffLoaderImageContext::new(test_synthetic_reader);
+    assert_eq!(image_context.update_info_from_headers(), Err(PeCoffImageError::ImageErrorInvalidPeHeaderSignature));
+  }
+
+  // Sets up a single-fixup HIGHLOW relocation block for a synthetic image,
+  // loads it at an address other than its preferred base, relocates it, and
+  // confirms the fixed-up value at the target RVA reflects the real delta
+  // between destination_address and image_base -- exercising load_image()
+  // and relocate_image() together the way a real loader would.
+  #[test]
+  fn relocate_applies_a_highlow_fixup_in_a_valid_block() {
+    const ORIGINAL_VALUE: u32 = 0x1122_3344;
+    const FIXUP_PAGE_RVA: u32 = 0x1000;
+    const HIGHLOW_ENTRY: u16 = (3 << 12) | 0; // EFI_IMAGE_REL_BASED_HIGHLOW, page_offset 0
+
+    let mut text_section = vec![0u8; 0x1000];
+    text_section[0..4].copy_from_slice(&ORIGINAL_VALUE.to_le_bytes());
+
+    let mut relocation_block = Vec::new();
+    relocation_block.extend_from_slice(&FIXUP_PAGE_RVA.to_le_bytes()); // VirtualAddress
+    relocation_block.extend_from_slice(&10u32.to_le_bytes()); // SizeOfBlock (8 + one 2-byte entry)
+    relocation_block.extend_from_slice(&HIGHLOW_ENTRY.to_le_bytes());
+
+    // image_base of 0 guarantees the destination buffer (a real, non-null
+    // heap allocation) lands at a nonzero delta, so the fixup path actually
+    // runs rather than taking the "already at preferred base" shortcut.
+    let image = build_synthetic_pe_image(0, (0x2000, relocation_block.len() as u32), (0, 0),
+                                          &text_section, b"junkjunk", &relocation_block);
+    unsafe { SYNTHETIC_IMAGE = image; }
+
+    let mut image_context = PeCoffLoaderImageContext::new(test_synthetic_reader);
+    assert!(image_context.update_info_from_headers().is_ok());
+    assert_eq!(image_context.relocations_stripped, base::Boolean::FALSE);
+
+    let mut destination = vec![0u8; image_context.image_size as usize];
+    image_context.destination_address = destination.as_mut_ptr() as u64;
+    assert!(image_context.load_image().is_ok());
+
+    let delta = image_context.destination_address;
+    assert!(delta != 0);
+    assert!(image_context.relocate_image().is_ok());
+
+    let relocated = unsafe {
+      core::ptr::read_unaligned((destination.as_ptr() as u64 + FIXUP_PAGE_RVA as u64) as *const u32)
+    };
+    assert_eq!(relocated, ORIGINAL_VALUE.wrapping_add(delta as u32));
+  }
+
+  // Covers the first of relocate_image()'s two malformed-block checks: a block
+  // whose SizeOfBlock claims more bytes than remain in the relocation
+  // directory must be rejected rather than trusted to size the entry walk.
+  #[test]
+  fn relocate_image_rejects_a_relocation_block_that_overruns_the_directory() {
+    let mut relocation_block = Vec::new();
+    relocation_block.extend_from_slice(&0x1000u32.to_le_bytes()); // VirtualAddress
+    relocation_block.extend_from_slice(&0xffffu32.to_le_bytes()); // SizeOfBlock -- far larger than the 8 bytes below
+
+    let image = build_synthetic_pe_image(0, (0x2000, relocation_block.len() as u32), (0, 0),
+                                          &[0u8; 0x1000], b"junkjunk", &relocation_block);
+    unsafe { SYNTHETIC_IMAGE = image; }
+
+    let mut image_context = PeCoffLoaderImageContext::new(test_synthetic_reader);
+    assert!(image_context.update_info_from_headers().is_ok());
+
+    let mut destination = vec![0u8; image_context.image_size as usize];
+    image_context.destination_address = destination.as_mut_ptr() as u64;
+    assert_eq!(image_context.relocate_image(), Err(PeCoffImageError::ImageErrorFailedRelocation));
+  }
+
+  // Covers the second of relocate_image()'s two malformed-block checks: a fixup
+  // whose page_rva places it outside the image must be rejected rather than
+  // dereferenced.
+  #[test]
+  fn relocate_image_rejects_a_fixup_that_targets_outside_the_image() {
+    const HIGHLOW_ENTRY: u16 = (3 << 12) | 0;
+
+    let mut relocation_block = Vec::new();
+    relocation_block.extend_from_slice(&0xffff_0000u32.to_le_bytes()); // VirtualAddress -- far beyond image_size
+    relocation_block.extend_from_slice(&10u32.to_le_bytes()); // SizeOfBlock
+    relocation_block.extend_from_slice(&HIGHLOW_ENTRY.to_le_bytes());
+
+    let image = build_synthetic_pe_image(0, (0x2000, relocation_block.len() as u32), (0, 0),
+                                          &[0u8; 0x1000], b"junkjunk", &relocation_block);
+    unsafe { SYNTHETIC_IMAGE = image; }
+
+    let mut image_context = PeCoffLoaderImageContext::new(test_synthetic_reader);
+    assert!(image_context.update_info_from_headers().is_ok());
+
+    let mut destination = vec![0u8; image_context.image_size as usize];
+    image_context.destination_address = destination.as_mut_ptr() as u64;
+    assert_eq!(image_context.relocate_image(), Err(PeCoffImageError::ImageErrorFailedRelocation));
+  }
+
+  // A fixup whose starting RVA is inside the image can still have its tail
+  // fall off the end: a DIR64 (8-byte) fixup placed at image_size - 1 passes
+  // a "does the start fit" check but would write 7 bytes past the end of
+  // the destination buffer. Both the start and the full width of the fixup
+  // must be validated before it's dereferenced.
+  #[test]
+  fn relocate_image_rejects_a_fixup_whose_width_overruns_the_image() {
+    const DIR64_ENTRY: u16 = (10 << 12) | 0x0fff; // EFI_IMAGE_REL_BASED_DIR64, page_offset 0xfff
+
+    let mut relocation_block = Vec::new();
+    relocation_block.extend_from_slice(&0x2000u32.to_le_bytes()); // VirtualAddress (page_rva)
+    relocation_block.extend_from_slice(&10u32.to_le_bytes()); // SizeOfBlock
+    relocation_block.extend_from_slice(&DIR64_ENTRY.to_le_bytes());
+
+    // image_size for this synthetic image is always DATA_RVA + SECTION_ALIGNMENT
+    // (0x3000), so a fixup at page_rva 0x2000 + page_offset 0xfff = image_size - 1.
+    let image = build_synthetic_pe_image(0, (0x2000, relocation_block.len() as u32), (0, 0),
+                                          &[0u8; 0x1000], b"junkjunk", &relocation_block);
+    unsafe { SYNTHETIC_IMAGE = image; }
+
+    let mut image_context = PeCoffLoaderImageContext::new(test_synthetic_reader);
+    assert!(image_context.update_info_from_headers().is_ok());
+    assert_eq!(image_context.image_size, 0x3000);
+
+    let mut destination = vec![0u8; image_context.image_size as usize];
+    image_context.destination_address = destination.as_mut_ptr() as u64;
+    assert_eq!(image_context.relocate_image(), Err(PeCoffImageError::ImageErrorFailedRelocation));
+  }
+
+  // load_image() must reject a SizeOfHeaders larger than the image itself,
+  // rather than trust it to size the header copy.
+  #[test]
+  fn load_image_rejects_a_size_of_headers_larger_than_the_image() {
+    let image = build_synthetic_pe_image(0, (0, 0), (0, 0), &[0u8; 0x1000], b"junkjunk", &[0u8; 16]);
+    unsafe { SYNTHETIC_IMAGE = image; }
+
+    let mut image_context = PeCoffLoaderImageContext::new(test_synthetic_reader);
+    assert!(image_context.update_info_from_headers().is_ok());
+    image_context.size_of_headers = (image_context.image_size + 1) as usize;
+
+    let mut destination = vec![0u8; image_context.image_size as usize];
+    image_context.destination_address = destination.as_mut_ptr() as u64;
+    assert_eq!(image_context.load_image(), Err(PeCoffImageError::ImageErrorInvalidImageSize));
+  }
+
+  // load_image() must reject a section whose virtual_address/size extends
+  // past image_size, rather than copy it into an undersized destination.
+  #[test]
+  fn load_image_rejects_a_section_that_overruns_the_image() {
+    let image = build_synthetic_pe_image(0, (0, 0), (0, 0), &[0u8; 0x1000], b"junkjunk", &[0u8; 16]);
+    unsafe { SYNTHETIC_IMAGE = image; }
+
+    let mut image_context = PeCoffLoaderImageContext::new(test_synthetic_reader);
+    assert!(image_context.update_info_from_headers().is_ok());
+    // The second (data) section sits at RVA 0x2000 and runs to image_size
+    // (0x3000); shrinking image_size out from under it makes the section
+    // overrun what it's still claimed to fit in.
+    image_context.image_size = 0x2000;
+
+    let mut destination = vec![0u8; 0x2000];
+    image_context.destination_address = destination.as_mut_ptr() as u64;
+    assert_eq!(image_context.load_image(), Err(PeCoffImageError::ImageErrorInvalidImageSize));
+  }
+
+  // load_image() must reject a section whose virtual_address isn't a
+  // multiple of section_alignment.
+  #[test]
+  fn load_image_rejects_a_misaligned_section() {
+    let image = build_synthetic_pe_image(0, (0, 0), (0, 0), &[0u8; 0x1000], b"junkjunk", &[0u8; 16]);
+    unsafe { SYNTHETIC_IMAGE = image; }
+
+    let probe_context = PeCoffLoaderImageContext::new(test_synthetic_reader);
+    let header = probe_context.parse_pe_coff_header(DOS_HEADER_SIZE as u32).unwrap();
+    // Each RawSection header lays its VirtualAddress field 12 bytes in
+    // (an 8-byte Name followed by a 4-byte VirtualSize); misalign the first
+    // (.text) section's.
+    let virtual_address_offset = header.section_table_offset + 12;
+    unsafe {
+      SYNTHETIC_IMAGE[virtual_address_offset..virtual_address_offset + 4].copy_from_slice(&0x1001u32.to_le_bytes());
+    }
+
+    let mut image_context = PeCoffLoaderImageContext::new(test_synthetic_reader);
+    assert!(image_context.update_info_from_headers().is_ok());
+
+    let mut destination = vec![0u8; image_context.image_size as usize];
+    image_context.destination_address = destination.as_mut_ptr() as u64;
+    assert_eq!(image_context.load_image(), Err(PeCoffImageError::ImageErrorInvalidSectionAlignment));
+  }
+
+  // load_image() must both copy a section's raw file bytes to its RVA and
+  // zero-fill whatever's left of VirtualSize beyond them (e.g. a section's
+  // .bss tail, which carries no raw data of its own).
+  #[test]
+  fn load_image_copies_section_data_and_zero_fills_the_uninitialized_tail() {
+    const DATA_RVA: u64 = 0x2000;
+    let content = vec![0xaau8; 16];
+
+    let image = build_synthetic_pe_image(0, (0, 0), (0, 0), &[0u8; 0x1000], b"junkjunk", &content);
+    unsafe { SYNTHETIC_IMAGE = image; }
+
+    let mut image_context = PeCoffLoaderImageContext::new(test_synthetic_reader);
+    assert!(image_context.update_info_from_headers().is_ok());
+
+    let mut destination = vec![0u8; image_context.image_size as usize];
+    image_context.destination_address = destination.as_mut_ptr() as u64;
+    assert!(image_context.load_image().is_ok());
+
+    let data_section_start = DATA_RVA as usize;
+    assert_eq!(&destination[data_section_start..data_section_start + content.len()], &content[..]);
+    assert!(destination[data_section_start + content.len()..data_section_start + 0x1000].iter().all(|byte| *byte == 0));
+  }
+
+  // Builds a minimal, well-formed TE image: the fixed 40-byte
+  // EFI_TE_IMAGE_HEADER followed by one section table entry and that
+  // section's raw bytes.
+  fn build_synthetic_te_image(machine: u16, entry_point: u32, image_base: u64) -> Vec<u8> {
+    const SECTION_RVA: u32 = 0x1000;
+
+    let mut image = Vec::new();
+    image.extend_from_slice(&TE_IMAGE_HEADER_SIGNATURE.to_le_bytes());
+    image.extend_from_slice(&machine.to_le_bytes());
+    image.push(1); // NumberOfSections
+    image.push(IMAGE_SUBSYSTEM_EFI_APPLICATION as u8);
+    image.extend_from_slice(&(TE_IMAGE_HEADER_SIZE as u16).to_le_bytes()); // StrippedSize
+    image.extend_from_slice(&entry_point.to_le_bytes());
+    image.extend_from_slice(&0u32.to_le_bytes()); // BaseOfCode
+    image.extend_from_slice(&image_base.to_le_bytes());
+    image.extend_from_slice(&[0u8; 8]); // base relocation directory (none)
+    image.extend_from_slice(&[0u8; 8]); // debug directory (none)
+    assert_eq!(image.len(), TE_IMAGE_HEADER_SIZE);
+
+    let mut section = b".text\0\0\0".to_vec();
+    section.extend_from_slice(&0x1000u32.to_le_bytes()); // VirtualSize
+    section.extend_from_slice(&SECTION_RVA.to_le_bytes());
+    section.extend_from_slice(&0x1000u32.to_le_bytes()); // SizeOfRawData
+    section.extend_from_slice(&(TE_IMAGE_HEADER_SIZE as u32).to_le_bytes()); // PointerToRawData
+    section.extend_from_slice(&[0u8; 12]);
+    section.extend_from_slice(&[0u8; 4]); // Characteristics
+
+    image.extend_from_slice(&section);
+    image.resize(TE_IMAGE_HEADER_SIZE + 0x1000, 0);
+    image
+  }
+
+  #[test]
+  fn update_info_from_headers_parses_a_te_image() {
+    let image = build_synthetic_te_image(IMAGE_FILE_MACHINE_X64, 0x20, 0x1_4000_0000);
+    unsafe { SYNTHETIC_IMAGE = image; }
+
+    let mut image_context = PeCoffLoaderImageContext::new(test_synthetic_reader);
+    assert!(image_context.update_info_from_headers().is_ok());
+    assert_eq!(image_context.is_te_image, base::Boolean::TRUE);
+    assert_eq!(image_context.machine, IMAGE_FILE_MACHINE_X64);
+    assert_eq!(image_context.entry_point, 0x20);
+    assert_eq!(image_context.relocations_stripped, base::Boolean::TRUE);
+    // No stripping applies here (BaseOfCode == 0), so SizeOfHeaders is just
+    // the fixed TE header.
+    assert_eq!(image_context.size_of_headers, TE_IMAGE_HEADER_SIZE);
+  }
+
+  // update_info_from_te_header() must reject a NumberOfSections beyond
+  // MAX_SECTION_COUNT, the same as the PE/COFF path does.
+  #[test]
+  fn update_info_from_te_header_rejects_an_oversized_section_count() {
+    let mut image = build_synthetic_te_image(IMAGE_FILE_MACHINE_X64, 0x20, 0x1_4000_0000);
+    // NumberOfSections is the single byte following the 2-byte signature and
+    // 2-byte machine fields.
+    image[4] = (MAX_SECTION_COUNT + 1) as u8;
+    unsafe { SYNTHETIC_IMAGE = image; }
+
+    let mut image_context = PeCoffLoaderImageContext::new(test_synthetic_reader);
+    assert_eq!(image_context.update_info_from_headers(), Err(PeCoffImageError::ImageErrorInvalidImageSize));
+  }
+
+  // A TE transform with a StrippedSize beyond the fixed TE header (i.e. one
+  // that also stripped part of the original DOS/PE/COFF header and section
+  // table) leaves every section's PointerToRawData expressed in terms of the
+  // original, unstripped file -- sections_for_relocation()'s
+  // file_offset_adjustment is what corrects that back to real offsets in the
+  // truncated file this loader actually reads. Exercises relocate_image(),
+  // load_image(), and section_data() together against a fixture with a
+  // nonzero stripped offset, the same way relocate_applies_a_highlow_fixup_in_a_valid_block
+  // exercises relocate_image() and load_image() on the plain PE path
+  // (file_offset_adjustment == 0).
+  #[test]
+  fn relocate_and_load_apply_the_te_stripped_offset_correction() {
+    const STRIP_EXTRA: u16 = 0x40;
+    const TEXT_RVA: u32 = 0x1000;
+    const DATA_RVA: u32 = 0x2000;
+    const ORIGINAL_VALUE: u32 = 0x1122_3344;
+    const FIXUP_PAGE_RVA: u32 = TEXT_RVA;
+    const HIGHLOW_ENTRY: u16 = (3 << 12) | 0; // EFI_IMAGE_REL_BASED_HIGHLOW, page_offset 0
+
+    let mut relocation_block = Vec::new();
+    relocation_block.extend_from_slice(&FIXUP_PAGE_RVA.to_le_bytes()); // VirtualAddress
+    relocation_block.extend_from_slice(&10u32.to_le_bytes()); // SizeOfBlock (8 + one 2-byte entry)
+    relocation_block.extend_from_slice(&HIGHLOW_ENTRY.to_le_bytes());
+
+    let stripped_size = TE_IMAGE_HEADER_SIZE as u16 + STRIP_EXTRA;
+    let stripped_offset = STRIP_EXTRA as u32;
+
+    let mut image = Vec::new();
+    image.extend_from_slice(&TE_IMAGE_HEADER_SIGNATURE.to_le_bytes());
+    image.extend_from_slice(&IMAGE_FILE_MACHINE_X64.to_le_bytes());
+    image.push(2); // NumberOfSections
+    image.push(IMAGE_SUBSYSTEM_EFI_APPLICATION as u8);
+    image.extend_from_slice(&stripped_size.to_le_bytes());
+    image.extend_from_slice(&TEXT_RVA.to_le_bytes()); // AddressOfEntryPoint
+    image.extend_from_slice(&0u32.to_le_bytes()); // BaseOfCode -- no headers beyond the TE header itself
+    image.extend_from_slice(&0u64.to_le_bytes()); // ImageBase -- 0 guarantees a nonzero relocation delta
+    image.extend_from_slice(&DATA_RVA.to_le_bytes()); // base relocation directory VirtualAddress
+    image.extend_from_slice(&(relocation_block.len() as u32).to_le_bytes()); // base relocation directory Size
+    image.extend_from_slice(&[0u8; 8]); // debug directory (none)
+    assert_eq!(image.len(), TE_IMAGE_HEADER_SIZE);
+
+    // PointerToRawData is expressed in terms of the original, unstripped
+    // file, so it's the actual offset in this (already-stripped) file plus
+    // stripped_offset -- the correction sections_for_relocation() is
+    // responsible for undoing via file_offset_adjustment.
+    let section_header = |name: &[u8; 8], virtual_address: u32, size_of_raw_data: u32, actual_file_offset: u32| -> Vec<u8> {
+      let mut header = name.to_vec();
+      header.extend_from_slice(&size_of_raw_data.to_le_bytes()); // VirtualSize
+      header.extend_from_slice(&virtual_address.to_le_bytes());
+      header.extend_from_slice(&size_of_raw_data.to_le_bytes());
+      header.extend_from_slice(&(actual_file_offset + stripped_offset).to_le_bytes()); // PointerToRawData
+      header.extend_from_slice(&[0u8; 12]);
+      header.extend_from_slice(&[0u8; 4]); // Characteristics
+      header
+    };
+
+    let header_region_size = TE_IMAGE_HEADER_SIZE + 2 * IMAGE_SECTION_HEADER_SIZE;
+    let text_file_offset = header_region_size as u32;
+    let data_file_offset = text_file_offset + 0x1000;
+
+    image.extend_from_slice(&section_header(b".text\0\0\0", TEXT_RVA, 0x1000, text_file_offset));
+    image.extend_from_slice(&section_header(b".reloc\0\0", DATA_RVA, relocation_block.len() as u32, data_file_offset));
+    assert_eq!(image.len(), header_region_size);
+
+    let mut text_section = vec![0u8; 0x1000];
+    text_section[0..4].copy_from_slice(&ORIGINAL_VALUE.to_le_bytes());
+    image.extend_from_slice(&text_section);
+    image.extend_from_slice(&relocation_block);
+    unsafe { SYNTHETIC_IMAGE = image; }
+
+    let mut image_context = PeCoffLoaderImageContext::new(test_synthetic_reader);
+    assert!(image_context.update_info_from_headers().is_ok());
+    assert_eq!(image_context.is_te_image, base::Boolean::TRUE);
+    assert_eq!(image_context.relocations_stripped, base::Boolean::FALSE);
+
+    assert_eq!(image_context.section_data(".reloc").unwrap(), relocation_block);
+
+    let mut destination = vec![0u8; image_context.image_size as usize];
+    image_context.destination_address = destination.as_mut_ptr() as u64;
+    assert!(image_context.load_image().is_ok());
+
+    let delta = image_context.destination_address;
+    assert!(delta != 0);
+    assert!(image_context.relocate_image().is_ok());
+
+    let relocated = unsafe {
+      core::ptr::read_unaligned((destination.as_ptr() as u64 + FIXUP_PAGE_RVA as u64) as *const u32)
+    };
+    assert_eq!(relocated, ORIGINAL_VALUE.wrapping_add(delta as u32));
+  }
+
+  // section_data() is how stub loaders pull their custom ".cmdline"/".linux"/
+  // etc. sections; it should find a present section by name and report
+  // ImageErrorSectionNotLoaded for one that isn't there.
+  #[test]
+  fn section_data_finds_a_named_section_and_reports_a_missing_one() {
+    let content = b"console=ttyS0\0".to_vec();
+    let image = build_synthetic_pe_image(0, (0, 0), (0, 0), &[0u8; 0x1000], b".cmdline", &content);
+    unsafe { SYNTHETIC_IMAGE = image; }
+
+    let mut image_context = PeCoffLoaderImageContext::new(test_synthetic_reader);
+    assert!(image_context.update_info_from_headers().is_ok());
+
+    let data = image_context.section_data(".cmdline").expect("section should be present");
+    assert_eq!(data, content);
+    assert_eq!(image_context.section_data_as_string(".cmdline").unwrap(), "console=ttyS0");
+
+    assert_eq!(image_context.section_data(".missing"), Err(PeCoffImageError::ImageErrorSectionNotLoaded));
+  }
+
+  // A section with a near-u32::MAX virtual address must be rejected rather
+  // than let `virtual_address + section_size` overflow (a debug-build panic,
+  // or a release-build wraparound to an attacker-influenced file offset).
+  #[test]
+  fn rva_to_file_offset_rejects_a_section_whose_bounds_overflow() {
+    let section = RawSection {
+      name:                [0u8; 8],
+      virtual_address:     0xffff_f000,
+      virtual_size:        0x2000, // virtual_address + virtual_size overflows u32
+      size_of_raw_data:    0x2000,
+      pointer_to_raw_data: 0x400,
+    };
+
+    assert_eq!(PeCoffLoaderImageContext::rva_to_file_offset(&[section], 0xffff_f100, 0), None);
+  }
+
+  // update_debug_directory_info()/pdb_path() should find an RSDS CodeView
+  // record via the debug directory and read its NUL-terminated PDB path back
+  // out of the image at its runtime image_address -- exercised end-to-end
+  // through load_image() so pdb_pointer resolves against real memory.
+  #[test]
+  fn update_info_from_headers_extracts_codeview_debug_info() {
+    const DATA_RVA: u32 = 0x2000;
+    let pdb_path = b"Z:\\build\\RngDxe.pdb\0";
+    let codeview_rva = DATA_RVA + IMAGE_DEBUG_DIRECTORY_ENTRY_SIZE as u32;
+
+    let mut codeview = CODEVIEW_SIGNATURE_RSDS.to_le_bytes().to_vec();
+    codeview.extend_from_slice(&[0u8; 16]); // GUID
+    codeview.extend_from_slice(&1u32.to_le_bytes()); // Age
+    codeview.extend_from_slice(pdb_path);
+
+    let mut debug_entry = Vec::new();
+    debug_entry.extend_from_slice(&[0u8; 4]); // Characteristics
+    debug_entry.extend_from_slice(&[0u8; 4]); // TimeDateStamp
+    debug_entry.extend_from_slice(&[0u8; 2]); // MajorVersion
+    debug_entry.extend_from_slice(&[0u8; 2]); // MinorVersion
+    debug_entry.extend_from_slice(&IMAGE_DEBUG_TYPE_CODEVIEW.to_le_bytes());
+    debug_entry.extend_from_slice(&(codeview.len() as u32).to_le_bytes()); // SizeOfData
+    debug_entry.extend_from_slice(&codeview_rva.to_le_bytes()); // RVA
+    debug_entry.extend_from_slice(&[0u8; 4]); // FileOffset (unused by this loader)
+    assert_eq!(debug_entry.len(), IMAGE_DEBUG_DIRECTORY_ENTRY_SIZE);
+
+    let mut data_section = debug_entry.clone();
+    data_section.extend_from_slice(&codeview);
+
+    let image = build_synthetic_pe_image(0, (0, 0), (DATA_RVA, debug_entry.len() as u32),
+                                          &[0u8; 0x1000], b"junkjunk", &data_section);
+    unsafe { SYNTHETIC_IMAGE = image; }
+
+    let mut backing = vec![0u8; 0x3000];
+    let base_address = backing.as_mut_ptr() as u64;
+    let mut image_context = PeCoffLoaderImageContext::new(test_synthetic_reader);
+    // image_address/destination_address aren't set until after GetImageInfo,
+    // mirroring the real caller sequence -- code_view/pdb_pointer must stay
+    // unresolved until load_image() runs against a live image_address.
+    assert!(image_context.update_info_from_headers().is_ok());
+    assert_eq!(image_context.debug_directory_entry_rva, DATA_RVA);
+    assert!(image_context.code_view.is_null());
+    assert!(image_context.pdb_pointer.is_null());
+
+    image_context.image_address = base_address;
+    image_context.destination_address = base_address;
+    assert!(image_context.load_image().is_ok());
+    assert_eq!(image_context.pdb_path().unwrap(), "Z:\\build\\RngDxe.pdb");
+  }
+
+  // resolve_debug_directory_pointers() must reject a debug_directory_entry_rva
+  // that would read past image_size once the image is mapped, rather than
+  // dereference straight past the destination buffer.
+  #[test]
+  fn resolve_debug_directory_pointers_rejects_an_out_of_bounds_rva() {
+    let image = build_synthetic_pe_image(0, (0, 0), (0, 0), &[0u8; 0x1000], b"junkjunk", &[0u8; 16]);
+    unsafe { SYNTHETIC_IMAGE = image; }
+
+    let mut backing = vec![0u8; 0x3000];
+    let base_address = backing.as_mut_ptr() as u64;
+    let mut image_context = PeCoffLoaderImageContext::new(test_synthetic_reader);
+    assert!(image_context.update_info_from_headers().is_ok());
+
+    // A debug_directory_entry_rva of image_size - 4 would read a 28-byte
+    // (IMAGE_DEBUG_DIRECTORY_ENTRY_SIZE) entry, 24 bytes of it past the end
+    // of the mapped image.
+    image_context.debug_directory_entry_rva = (image_context.image_size - 4) as u32;
+
+    image_context.image_address = base_address;
+    image_context.destination_address = base_address;
+    assert!(image_context.load_image().is_ok());
+    assert!(image_context.code_view.is_null());
+    assert!(image_context.pdb_pointer.is_null());
+  }
+
+  // update_debug_directory_info() must reject a debug_directory.size larger
+  // than the image before trusting it to size the entry_count loop, rather
+  // than iterate over a made-up number of debug directory entries.
+  #[test]
+  fn update_info_from_headers_rejects_an_oversized_debug_directory_size() {
+    let image = build_synthetic_pe_image(0, (0, 0), (0x2000, u32::MAX), &[0u8; 0x1000], b"junkjunk", &[0u8; 16]);
+    unsafe { SYNTHETIC_IMAGE = image; }
+
+    let mut image_context = PeCoffLoaderImageContext::new(test_synthetic_reader);
+    assert!(image_context.update_info_from_headers().is_ok());
+    assert_eq!(image_context.debug_directory_entry_rva, 0);
+  }
+
+  // pdb_path()'s NUL scan must stop at the edge of the mapped image rather
+  // than walk past it looking for a terminator that was never loaded.
+  #[test]
+  fn pdb_path_rejects_a_path_with_no_nul_before_the_end_of_the_image() {
+    let mut backing = vec![0xffu8; 0x20];
+    let base_address = backing.as_mut_ptr() as u64;
+
+    let mut image_context = PeCoffLoaderImageContext::new(test_synthetic_reader);
+    image_context.image_address = base_address;
+    image_context.image_size = backing.len() as u64;
+    image_context.pdb_pointer = (base_address + backing.len() as u64 - 4) as *const u8;
+
+    assert_eq!(image_context.pdb_path(), Err(PeCoffImageError::ImageErrorUnsupported));
+  }
 }
\ No newline at end of file